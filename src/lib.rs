@@ -3,6 +3,98 @@
 use core::fmt::{Debug, Display};
 use log::{logger, Level};
 
+/// Like [`LogError::log_error`], but targets the caller's actual crate and
+/// module (via `module_path!()` captured at the call site) instead of the
+/// best-effort guess [`LogError::log_error`] derives from the caller's
+/// filesystem path, so `RUST_LOG=mycrate::worktree` reliably filters it.
+#[macro_export]
+macro_rules! log_error {
+    ($result:expr, $msg:expr) => {
+        $crate::LogError::log_error_target($result, module_path!(), $msg)
+    };
+}
+
+/// Like [`LogError::log_warn`], but targets the caller's actual crate and
+/// module (via `module_path!()` captured at the call site); see [`log_error!`].
+#[macro_export]
+macro_rules! log_warn {
+    ($result:expr, $msg:expr) => {
+        $crate::LogError::log_warn_target($result, module_path!(), $msg)
+    };
+}
+
+/// Like [`LogError::log_error_detail`], but targets the caller's actual crate
+/// and module (via `module_path!()` captured at the call site); see
+/// [`log_error!`].
+#[macro_export]
+macro_rules! log_error_detail {
+    ($result:expr, $msg:expr) => {
+        $crate::LogError::log_level_target_with(
+            $result,
+            module_path!(),
+            ::log::Level::Error,
+            |err| format!("{}: {:#?}", $msg, err),
+        )
+    };
+}
+
+/// Like [`LogError::log_warn_detail`], but targets the caller's actual crate
+/// and module (via `module_path!()` captured at the call site); see
+/// [`log_error!`].
+#[macro_export]
+macro_rules! log_warn_detail {
+    ($result:expr, $msg:expr) => {
+        $crate::LogError::log_level_target_with(
+            $result,
+            module_path!(),
+            ::log::Level::Warn,
+            |err| format!("{}: {:#?}", $msg, err),
+        )
+    };
+}
+
+/// Like [`LogError::log_error_kv`], but targets the caller's actual crate and
+/// module (via `module_path!()` captured at the call site); see
+/// [`log_error!`]. Requires the `kv` feature.
+#[cfg(feature = "kv")]
+#[macro_export]
+macro_rules! log_error_kv {
+    ($result:expr, $msg:expr) => {
+        $crate::LogError::log_level_kv_target($result, module_path!(), ::log::Level::Error, $msg)
+    };
+}
+
+/// Like [`LogError::log_warn_kv`], but targets the caller's actual crate and
+/// module (via `module_path!()` captured at the call site); see
+/// [`log_error!`]. Requires the `kv` feature.
+#[cfg(feature = "kv")]
+#[macro_export]
+macro_rules! log_warn_kv {
+    ($result:expr, $msg:expr) => {
+        $crate::LogError::log_level_kv_target($result, module_path!(), ::log::Level::Warn, $msg)
+    };
+}
+
+/// Like [`LogError::unwrap_or_log`], but targets the caller's actual crate and
+/// module (via `module_path!()` captured at the call site); see
+/// [`log_error!`].
+#[macro_export]
+macro_rules! unwrap_or_log {
+    ($result:expr) => {
+        $crate::LogError::unwrap_or_log_target($result, module_path!())
+    };
+}
+
+/// Like [`LogError::expect_or_log`], but targets the caller's actual crate and
+/// module (via `module_path!()` captured at the call site); see
+/// [`log_error!`].
+#[macro_export]
+macro_rules! expect_or_log {
+    ($result:expr, $msg:expr) => {
+        $crate::LogError::expect_or_log_target($result, module_path!(), $msg)
+    };
+}
+
 /// Trait to log the error result, there are impls for [`Result`] and [`Option`] by default.
 pub trait LogError<T, E>: Sized {
     /// log the error with specific log-level and format handler
@@ -61,6 +153,102 @@ pub trait LogError<T, E>: Sized {
     {
         self.log_warn_with(|err| format!("{msg}: {err:#?}"))
     }
+
+    /// log the error at `level`, recording `msg` as the message and attaching the
+    /// original error as a structured `error` key-value field so structured
+    /// backends (e.g. a `log`-to-`tracing` bridge) can index on it while plain
+    /// text loggers still print `msg`.
+    #[cfg(feature = "kv")]
+    fn log_level_kv(self, level: Level, msg: &str) -> Option<T>
+    where
+        E: Display;
+
+    /// log the error at [`Level::Error`] with a structured `error` field
+    #[cfg(feature = "kv")]
+    #[inline(always)]
+    #[track_caller]
+    fn log_error_kv(self, msg: &str) -> Option<T>
+    where
+        E: Display,
+    {
+        self.log_level_kv(Level::Error, msg)
+    }
+
+    /// log the error at [`Level::Warn`] with a structured `error` field
+    #[cfg(feature = "kv")]
+    #[inline(always)]
+    #[track_caller]
+    fn log_warn_kv(self, msg: &str) -> Option<T>
+    where
+        E: Display,
+    {
+        self.log_level_kv(Level::Warn, msg)
+    }
+
+    /// Like [`LogError::log_level_kv`], but logs to an explicit `target`
+    /// instead of the target derived from the call site; see
+    /// [`LogError::log_level_target_with`].
+    #[cfg(feature = "kv")]
+    fn log_level_kv_target(self, target: &str, level: Level, msg: &str) -> Option<T>
+    where
+        E: Display;
+
+    /// log the error at [`Level::Error`] then panic, surfacing the caller's
+    /// file/line in the log without requiring `RUST_BACKTRACE`
+    fn unwrap_or_log(self) -> T
+    where
+        E: Display;
+
+    /// log the error at [`Level::Error`] with `msg` then panic, surfacing the
+    /// caller's file/line in the log without requiring `RUST_BACKTRACE`
+    fn expect_or_log(self, msg: &str) -> T
+    where
+        E: Display;
+
+    /// log the error at `level` to an explicit `target` with the given format
+    /// handler, overriding the target derived from the call site so records can
+    /// be routed to a dedicated `RUST_LOG` selector independent of where the
+    /// call physically lives.
+    fn log_level_target_with<F: FnOnce(E) -> String>(
+        self,
+        target: &str,
+        level: Level,
+        cb: F,
+    ) -> Option<T>;
+
+    /// log the error with specific prefix to an explicit `target`
+    #[inline(always)]
+    #[track_caller]
+    fn log_error_target(self, target: &str, msg: &str) -> Option<T>
+    where
+        E: Display,
+    {
+        self.log_level_target_with(target, Level::Error, |err| format!("{msg}: {err}"))
+    }
+
+    /// log the error with specific prefix to an explicit `target` as a warn message
+    #[inline(always)]
+    #[track_caller]
+    fn log_warn_target(self, target: &str, msg: &str) -> Option<T>
+    where
+        E: Display,
+    {
+        self.log_level_target_with(target, Level::Warn, |err| format!("{msg}: {err}"))
+    }
+
+    /// Like [`LogError::unwrap_or_log`], but logs to an explicit `target`
+    /// instead of the target derived from the call site; see
+    /// [`LogError::log_level_target_with`].
+    fn unwrap_or_log_target(self, target: &str) -> T
+    where
+        E: Display;
+
+    /// Like [`LogError::expect_or_log`], but logs to an explicit `target`
+    /// instead of the target derived from the call site; see
+    /// [`LogError::log_level_target_with`].
+    fn expect_or_log_target(self, target: &str, msg: &str) -> T
+    where
+        E: Display;
 }
 
 /// Implements [`LogError`] for [`Result`]
@@ -71,7 +259,104 @@ impl<T, E> LogError<T, E> for Result<T, E> {
         match self {
             Ok(res) => Some(res),
             Err(err) => {
-                log_message(level, cb(err));
+                log_message(level, None, || cb(err));
+                None
+            }
+        }
+    }
+
+    #[cfg(feature = "kv")]
+    #[inline(always)]
+    #[track_caller]
+    fn log_level_kv(self, level: Level, msg: &str) -> Option<T>
+    where
+        E: Display,
+    {
+        match self {
+            Ok(res) => Some(res),
+            Err(err) => {
+                log_message_kv(level, None, msg, &err);
+                None
+            }
+        }
+    }
+
+    #[cfg(feature = "kv")]
+    #[inline(always)]
+    #[track_caller]
+    fn log_level_kv_target(self, target: &str, level: Level, msg: &str) -> Option<T>
+    where
+        E: Display,
+    {
+        match self {
+            Ok(res) => Some(res),
+            Err(err) => {
+                log_message_kv(level, Some(target), msg, &err);
+                None
+            }
+        }
+    }
+
+    #[inline(always)]
+    #[track_caller]
+    fn unwrap_or_log(self) -> T
+    where
+        E: Display,
+    {
+        match self {
+            Ok(res) => res,
+            Err(err) => unwrap_failed(None, "unwrapped an Err", &err),
+        }
+    }
+
+    #[inline(always)]
+    #[track_caller]
+    fn expect_or_log(self, msg: &str) -> T
+    where
+        E: Display,
+    {
+        match self {
+            Ok(res) => res,
+            Err(err) => unwrap_failed(None, msg, &err),
+        }
+    }
+
+    #[inline(always)]
+    #[track_caller]
+    fn unwrap_or_log_target(self, target: &str) -> T
+    where
+        E: Display,
+    {
+        match self {
+            Ok(res) => res,
+            Err(err) => unwrap_failed(Some(target), "unwrapped an Err", &err),
+        }
+    }
+
+    #[inline(always)]
+    #[track_caller]
+    fn expect_or_log_target(self, target: &str, msg: &str) -> T
+    where
+        E: Display,
+    {
+        match self {
+            Ok(res) => res,
+            Err(err) => unwrap_failed(Some(target), msg, &err),
+        }
+    }
+
+    #[inline(always)]
+    #[track_caller]
+    fn log_level_target_with<F: FnOnce(E) -> String>(
+        self,
+        target: &str,
+        level: Level,
+        cb: F,
+    ) -> Option<T> {
+        match self {
+            Ok(res) => Some(res),
+            Err(err) => {
+                log_message(level, Some(target), || cb(err));
                 None
             }
         }
@@ -86,7 +371,92 @@ impl<T> LogError<T, &'static str> for Option<T> {
         match self {
             Some(res) => Some(res),
             None => {
-                log_message(level, cb("None"));
+                log_message(level, None, || cb("None"));
+                None
+            }
+        }
+    }
+
+    #[cfg(feature = "kv")]
+    #[inline(always)]
+    #[track_caller]
+    fn log_level_kv(self, level: Level, msg: &str) -> Option<T>
+    where
+        &'static str: Display,
+    {
+        match self {
+            Some(res) => Some(res),
+            None => {
+                log_message_kv(level, None, msg, &"None");
+                None
+            }
+        }
+    }
+
+    #[cfg(feature = "kv")]
+    #[inline(always)]
+    #[track_caller]
+    fn log_level_kv_target(self, target: &str, level: Level, msg: &str) -> Option<T>
+    where
+        &'static str: Display,
+    {
+        match self {
+            Some(res) => Some(res),
+            None => {
+                log_message_kv(level, Some(target), msg, &"None");
+                None
+            }
+        }
+    }
+
+    #[inline(always)]
+    #[track_caller]
+    fn unwrap_or_log(self) -> T {
+        match self {
+            Some(res) => res,
+            None => unwrap_failed(None, "unwrapped a None", &"None"),
+        }
+    }
+
+    #[inline(always)]
+    #[track_caller]
+    fn expect_or_log(self, msg: &str) -> T {
+        match self {
+            Some(res) => res,
+            None => unwrap_failed(None, msg, &"None"),
+        }
+    }
+
+    #[inline(always)]
+    #[track_caller]
+    fn unwrap_or_log_target(self, target: &str) -> T {
+        match self {
+            Some(res) => res,
+            None => unwrap_failed(Some(target), "unwrapped a None", &"None"),
+        }
+    }
+
+    #[inline(always)]
+    #[track_caller]
+    fn expect_or_log_target(self, target: &str, msg: &str) -> T {
+        match self {
+            Some(res) => res,
+            None => unwrap_failed(Some(target), msg, &"None"),
+        }
+    }
+
+    #[inline(always)]
+    #[track_caller]
+    fn log_level_target_with<F: FnOnce(&'static str) -> String>(
+        self,
+        target: &str,
+        level: Level,
+        cb: F,
+    ) -> Option<T> {
+        match self {
+            Some(res) => Some(res),
+            None => {
+                log_message(level, Some(target), || cb("None"));
                 None
             }
         }
@@ -94,13 +464,77 @@ impl<T> LogError<T, &'static str> for Option<T> {
 }
 
 #[track_caller]
-fn log_message(level: Level, msg: String) {
+fn log_message<F: FnOnce() -> String>(level: Level, target: Option<&str>, cb: F) {
+    // Cheap, target-independent pre-check: skips the module derivation below
+    // (and `cb`'s formatting) entirely on the overwhelmingly common path where
+    // the level is disabled globally, mirroring the fast path `log`'s own
+    // macros take before consulting a target at all.
+    if level > log::max_level() {
+        return;
+    }
+
+    let loc = std::panic::Location::caller();
+    let file = loc.file();
+    // Only allocate the derived module when no explicit target was given, and
+    // only after the cheap check above passed.
+    let derived;
+    let target = match target {
+        Some(target) => target,
+        None => {
+            derived = module_from_file(file);
+            &derived
+        }
+    };
+
+    // Bail out before running `cb` so callers can keep heavyweight `{err:#?}`
+    // formatting in their closures at near-zero cost when the level is filtered
+    // out, mirroring the `log_enabled!` guard pattern.
+    if !log::log_enabled!(target: target, level) {
+        return;
+    }
+    let msg = cb();
+
+    logger().log(
+        &log::Record::builder()
+            .args(format_args!("{msg}"))
+            .file(Some(file))
+            .line(Some(loc.line()))
+            .level(level)
+            .target(target)
+            .module_path(Some(target))
+            .build(),
+    );
+}
+
+/// Like [`log_message`] but also attaches the error as a structured `error`
+/// key-value field, so structured subscribers keep the error as an indexable
+/// value instead of only the interpolated message.
+///
+/// `target` behaves exactly like in [`log_message`]: an explicit target
+/// overrides the one derived from the call site.
+#[cfg(feature = "kv")]
+#[track_caller]
+fn log_message_kv(level: Level, target: Option<&str>, msg: &str, err: &dyn Display) {
+    if level > log::max_level() {
+        return;
+    }
+
     let loc = std::panic::Location::caller();
     let file = loc.file();
-    let module = &file[file
-        .rfind(|c| c == '/' || c == '\\')
-        .map(|x| 1 + x)
-        .unwrap_or(0)..];
+    let derived;
+    let target = match target {
+        Some(target) => target,
+        None => {
+            derived = module_from_file(file);
+            &derived
+        }
+    };
+
+    if !log::log_enabled!(target: target, level) {
+        return;
+    }
+
+    let kvs = [("error", log::kv::Value::from_dyn_display(err))];
 
     logger().log(
         &log::Record::builder()
@@ -108,7 +542,79 @@ fn log_message(level: Level, msg: String) {
             .file(Some(file))
             .line(Some(loc.line()))
             .level(level)
-            .module_path(Some(module))
+            .target(target)
+            .module_path(Some(target))
+            .key_values(&kvs)
             .build(),
     );
 }
+
+/// log the error at [`Level::Error`] and then panic.
+///
+/// The message is prefixed with the caller's `file:line` (via `#[track_caller]`)
+/// so the failing site shows up in the log even without `RUST_BACKTRACE`; when
+/// the `backtrace` feature is enabled and `Backtrace::capture` actually records
+/// one (it only does so when `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE` enables
+/// it), that backtrace is appended so the panic path stays diagnosable through
+/// loggers that drop backtraces. Otherwise nothing is appended.
+///
+/// `target` behaves exactly like in [`log_message`]: an explicit target
+/// overrides the one derived from the call site.
+#[track_caller]
+fn unwrap_failed(target: Option<&str>, prefix: &str, err: &dyn Display) -> ! {
+    let loc = std::panic::Location::caller();
+    #[allow(unused_mut)]
+    let mut msg = format!("{}:{} {prefix}: {err}", loc.file(), loc.line());
+    #[cfg(feature = "backtrace")]
+    {
+        let backtrace = std::backtrace::Backtrace::capture();
+        if backtrace.status() == std::backtrace::BacktraceStatus::Captured {
+            msg = format!("{msg}\n{backtrace}");
+        }
+    }
+
+    log_message(Level::Error, target, || msg.clone());
+    panic!("{msg}");
+}
+
+/// Best-effort module path derived from the caller's filesystem path, used
+/// when a call site has no explicit (or macro-derived) target.
+///
+/// `#[track_caller]` only exposes a filesystem path, and under a normal cargo
+/// build that path is already package-relative (`src/lib.rs`,
+/// `src/worktree/mod.rs`) — there is no crate-name component to recover from
+/// it, so this can only ever reconstruct the `src/`-relative module path, e.g.
+/// `src/worktree/mod.rs` becomes `worktree`. It cannot produce a crate-prefixed
+/// path like `mycrate::worktree`; use the [`log_error!`]/[`log_warn!`]/...
+/// macros (or the `_target` methods) for that, since only a macro expanding at
+/// the call site can capture the caller's real `module_path!()`.
+fn module_from_file(file: &str) -> String {
+    let parts: Vec<&str> = file.split(['/', '\\']).collect();
+    // Only look at the part of the path at or after `src/`; fall back to the
+    // whole path when there's no recognizable `src/` boundary (e.g. `<anon>`).
+    let start = parts.iter().rposition(|&p| p == "src").map_or(0, |i| i + 1);
+    let sub = &parts[start..];
+
+    let mut segments: Vec<&str> = Vec::new();
+    for (i, seg) in sub.iter().enumerate() {
+        let last = i + 1 == sub.len();
+        let name = if last {
+            seg.strip_suffix(".rs").unwrap_or(seg)
+        } else {
+            seg
+        };
+        if last && segments.is_empty() {
+            // Sole component left, e.g. a crate-root `lib.rs`/`main.rs`: there
+            // is no deeper module to report, so keep the stripped stem instead
+            // of collapsing it away to nothing.
+            segments.push(name);
+        } else if last && matches!(name, "lib" | "main" | "mod") {
+            // Drop the conventional module-root file name; the parent
+            // directory segment already names the module.
+            continue;
+        } else {
+            segments.push(name);
+        }
+    }
+    segments.join("::")
+}